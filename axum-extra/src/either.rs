@@ -19,8 +19,14 @@
 //! # let _: axum::routing::MethodRouter = axum::routing::get(handler);
 //! ```
 //!
-//! Note that if all the inner extractors reject the request, the rejection from the last
-//! extractor will be returned. For the example above that would be [`BytesRejection`].
+//! The request body is buffered up front (bounded by [`DEFAULT_BUFFER_LIMIT`], configurable via
+//! [`EitherBufferLimit`]) so that body-consuming extractors such as [`Json`] each get their own
+//! copy of it to try, rather than only the first branch ever seeing a body. If all branches
+//! reject, every branch's rejection is kept (for the example above that would be an
+//! `Either3Rejection::AllRejected` holding a [`JsonRejection`], a [`StringRejection`] and a
+//! [`BytesRejection`]) so the failure can be inspected or logged in full, even though
+//! [`IntoResponse::into_response`] for the rejection only renders the most informative one of the
+//! three (falling back to the last if none of them stand out).
 //!
 //! # As a response
 //!
@@ -55,19 +61,184 @@
 //! The general recommendation is to use [`IntoResponse::into_response`] to return different response
 //! types, but if you need to preserve the exact type then `Either*` works as well.
 //!
+//! # Content-Type dispatch
+//!
+//! Speculatively trying each branch is wasteful for the common "JSON or form" case, where the
+//! `Content-Type` header already tells you which branch applies. Wrap an `Either*` in
+//! [`ByContentType`] to dispatch straight to the matching branch instead:
+//!
+//! ```
+//! use axum_extra::either::{ByContentType, Either};
+//! use axum::{Form, Json};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Payload {
+//!     # #[allow(dead_code)]
+//!     name: String,
+//! }
+//!
+//! async fn handler(
+//!     ByContentType(body): ByContentType<Either<Json<Payload>, Form<Payload>>>,
+//! ) {
+//!     match body {
+//!         Either::E1(json) => { /* ... */ }
+//!         Either::E2(form) => { /* ... */ }
+//!     }
+//! }
+//! #
+//! # let _: axum::routing::MethodRouter = axum::routing::get(handler);
+//! ```
+//!
+//! Each branch must implement [`AcceptsContentType`] to advertise the media type(s) it handles.
+//! `Json` and `Form` are covered out of the box; `Bytes` and `String` accept anything, so they
+//! should only ever be the last branch. A request whose `Content-Type` matches no branch is
+//! rejected with `415 Unsupported Media Type` naming the content types that would have worked.
+//!
+//! # Combinators
+//!
+//! [`Either`] and friends aren't just extractors and responses; `map_e1`..`map_eN` transform a
+//! single branch in place, and the two-variant [`Either`] additionally has [`Either::left`],
+//! [`Either::right`] and [`Either::flip`]. An `either` feature is planned so that the two-variant
+//! [`Either`] can convert to and from [`either::Either`](https://docs.rs/either/latest/either/enum.Either.html)
+//! and be handed off to that crate's combinator API, but the feature isn't declared in this
+//! crate's manifest yet, so it isn't available until it is.
+//!
 //! [`BytesRejection`]: axum::extract::rejection::BytesRejection
+//! [`Json`]: axum::Json
+//! [`JsonRejection`]: axum::extract::rejection::JsonRejection
+//! [`StringRejection`]: axum::extract::rejection::StringRejection
 //! [`IntoResponse::into_response`]: https://docs.rs/axum/0.5/axum/response/index.html#returning-different-response-types
 
 use axum::{
     async_trait,
+    body::{Bytes, HttpBody},
     extract::{FromRequest, RequestParts},
     response::{IntoResponse, Response},
+    BoxError,
 };
+use bytes::Buf;
+use http::{header, StatusCode};
+use std::{fmt, pin::Pin};
+
+/// The default maximum number of bytes that `Either*` extractors will buffer from the request
+/// body in order to retry it against each candidate branch.
+///
+/// Override this for a single request by inserting [`EitherBufferLimit`] as a request extension.
+pub const DEFAULT_BUFFER_LIMIT: usize = 2 * 1024 * 1024; // 2mb
+
+/// Request extension used to override [`DEFAULT_BUFFER_LIMIT`] for the `Either*` extractors.
+#[derive(Debug, Clone, Copy)]
+pub struct EitherBufferLimit(pub usize);
+
+/// Rejection used when the request body can't be buffered while trying the branches of an
+/// `Either*` extractor.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum EitherBodyRejection {
+    /// The request body was larger than the configured limit.
+    ///
+    /// See [`EitherBufferLimit`] to configure the limit for a request.
+    TooLarge,
+    /// Buffering the request body failed.
+    FailedToBufferBody(axum::Error),
+}
+
+impl fmt::Display for EitherBodyRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLarge => {
+                write!(f, "request body too large to buffer for `Either` extraction")
+            }
+            Self::FailedToBufferBody(err) => write!(f, "failed to buffer request body: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for EitherBodyRejection {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::TooLarge => None,
+            Self::FailedToBufferBody(err) => Some(err),
+        }
+    }
+}
+
+impl IntoResponse for EitherBodyRejection {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            Self::TooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::FailedToBufferBody(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
+
+/// Buffer the request body, bounded by `limit`, so it can be cloned for each branch of an
+/// `Either*` extractor. A `Content-Length` header larger than `limit` is rejected before any of
+/// the body is read.
+///
+/// The body is read incrementally and the running total is checked against `limit` after every
+/// chunk, so a chunked-encoded request with no (or a lying) `Content-Length` header still can't
+/// force more than `limit` bytes into memory.
+async fn buffer_body<S, B>(
+    req: &mut RequestParts<S, B>,
+    limit: usize,
+) -> Result<Bytes, EitherBodyRejection>
+where
+    B: HttpBody + Unpin,
+    B::Error: Into<BoxError>,
+{
+    if let Some(len) = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok())
+    {
+        if len > limit {
+            return Err(EitherBodyRejection::TooLarge);
+        }
+    }
+
+    let mut body = req
+        .take_body()
+        .expect("the request body was already extracted");
+
+    let mut collected = Vec::new();
+    while let Some(chunk) = std::future::poll_fn(|cx| Pin::new(&mut body).poll_data(cx))
+        .await
+        .transpose()
+        .map_err(|err| EitherBodyRejection::FailedToBufferBody(axum::Error::new(err)))?
+    {
+        if collected.len() + chunk.chunk().len() > limit {
+            return Err(EitherBodyRejection::TooLarge);
+        }
+        collected.extend_from_slice(chunk.chunk());
+    }
+
+    Ok(Bytes::from(collected))
+}
+
+/// Clone everything about `req` except its body, and attach `body` in its place.
+fn clone_request_parts<S, B>(req: &RequestParts<S, B>, body: B) -> RequestParts<S, B>
+where
+    S: Clone,
+{
+    let mut builder = http::Request::builder()
+        .method(req.method().clone())
+        .uri(req.uri().clone())
+        .version(req.version());
+    *builder.headers_mut().expect("request builder is valid") = req.headers().clone();
+    *builder.extensions_mut().expect("request builder is valid") = req.extensions().clone();
+    let request = builder.body(body).expect("request builder is valid");
+
+    RequestParts::new(req.state().clone(), request)
+}
 
 /// Combines two extractors or responses into a single type.
 ///
 /// See the [module docs](self) for examples.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Either<E1, E2> {
     #[allow(missing_docs)]
     E1(E1),
@@ -75,10 +246,68 @@ pub enum Either<E1, E2> {
     E2(E2),
 }
 
+impl<E1, E2> Either<E1, E2> {
+    /// Returns the first branch's value, if this is that branch.
+    pub fn left(self) -> Option<E1> {
+        match self {
+            Self::E1(value) => Some(value),
+            Self::E2(_) => None,
+        }
+    }
+
+    /// Returns the second branch's value, if this is that branch.
+    pub fn right(self) -> Option<E2> {
+        match self {
+            Self::E1(_) => None,
+            Self::E2(value) => Some(value),
+        }
+    }
+
+    /// Swaps the two branches.
+    pub fn flip(self) -> Either<E2, E1> {
+        match self {
+            Self::E1(value) => Either::E2(value),
+            Self::E2(value) => Either::E1(value),
+        }
+    }
+}
+
+// This crate's `Cargo.toml` is not part of this checkout, so the manifest side of this (adding
+// `either` as an optional dependency and declaring the `either` feature below) can't be done from
+// here. A maintainer with the manifest needs to add, before this can be merged:
+//     [dependencies]
+//     either = { version = "1", optional = true }
+//     [features]
+//     either = ["dep:either"]
+
+/// Converts from [`either::Either`](https://docs.rs/either/latest/either/enum.Either.html), so
+/// that crate's combinator API can be used before converting into this one.
+#[cfg(feature = "either")]
+impl<L, R> From<either::Either<L, R>> for Either<L, R> {
+    fn from(value: either::Either<L, R>) -> Self {
+        match value {
+            either::Either::Left(value) => Self::E1(value),
+            either::Either::Right(value) => Self::E2(value),
+        }
+    }
+}
+
+/// Converts into [`either::Either`](https://docs.rs/either/latest/either/enum.Either.html), so
+/// that crate's combinator API can be used after extracting or before responding.
+#[cfg(feature = "either")]
+impl<L, R> From<Either<L, R>> for either::Either<L, R> {
+    fn from(value: Either<L, R>) -> Self {
+        match value {
+            Either::E1(value) => Self::Left(value),
+            Either::E2(value) => Self::Right(value),
+        }
+    }
+}
+
 /// Combines three extractors or responses into a single type.
 ///
 /// See the [module docs](self) for examples.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Either3<E1, E2, E3> {
     #[allow(missing_docs)]
     E1(E1),
@@ -91,7 +320,7 @@ pub enum Either3<E1, E2, E3> {
 /// Combines four extractors or responses into a single type.
 ///
 /// See the [module docs](self) for examples.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Either4<E1, E2, E3, E4> {
     #[allow(missing_docs)]
     E1(E1),
@@ -106,7 +335,7 @@ pub enum Either4<E1, E2, E3, E4> {
 /// Combines five extractors or responses into a single type.
 ///
 /// See the [module docs](self) for examples.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Either5<E1, E2, E3, E4, E5> {
     #[allow(missing_docs)]
     E1(E1),
@@ -123,7 +352,7 @@ pub enum Either5<E1, E2, E3, E4, E5> {
 /// Combines six extractors or responses into a single type.
 ///
 /// See the [module docs](self) for examples.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Either6<E1, E2, E3, E4, E5, E6> {
     #[allow(missing_docs)]
     E1(E1),
@@ -142,7 +371,7 @@ pub enum Either6<E1, E2, E3, E4, E5, E6> {
 /// Combines seven extractors or responses into a single type.
 ///
 /// See the [module docs](self) for examples.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Either7<E1, E2, E3, E4, E5, E6, E7> {
     #[allow(missing_docs)]
     E1(E1),
@@ -163,7 +392,7 @@ pub enum Either7<E1, E2, E3, E4, E5, E6, E7> {
 /// Combines eight extractors or responses into a single type.
 ///
 /// See the [module docs](self) for examples.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Either8<E1, E2, E3, E4, E5, E6, E7, E8> {
     #[allow(missing_docs)]
     E1(E1),
@@ -183,30 +412,190 @@ pub enum Either8<E1, E2, E3, E4, E5, E6, E7, E8> {
     E8(E8),
 }
 
+macro_rules! impl_combinators_for_either {
+    ($either:ident<$($all:ident),+> => $(
+        $method:ident($target:ident): [$($before:ident),*] | [$($after:ident),*]
+    ),+ $(,)?) => {
+        impl<$($all),+> $either<$($all),+> {
+            $(
+                #[doc = concat!(
+                    "Maps the `", stringify!($target), "` branch's value, leaving the others untouched."
+                )]
+                pub fn $method<F, T>(self, f: F) -> $either<$($before,)* T, $($after),*>
+                where
+                    F: FnOnce($target) -> T,
+                {
+                    match self {
+                        $( Self::$before(value) => $either::$before(value), )*
+                        Self::$target(value) => $either::$target(f(value)),
+                        $( Self::$after(value) => $either::$after(value), )*
+                    }
+                }
+            )+
+        }
+    };
+}
+
+impl_combinators_for_either!(Either<E1, E2> =>
+    map_e1(E1): [] | [E2],
+    map_e2(E2): [E1] | [],
+);
+
+impl_combinators_for_either!(Either3<E1, E2, E3> =>
+    map_e1(E1): [] | [E2, E3],
+    map_e2(E2): [E1] | [E3],
+    map_e3(E3): [E1, E2] | [],
+);
+
+impl_combinators_for_either!(Either4<E1, E2, E3, E4> =>
+    map_e1(E1): [] | [E2, E3, E4],
+    map_e2(E2): [E1] | [E3, E4],
+    map_e3(E3): [E1, E2] | [E4],
+    map_e4(E4): [E1, E2, E3] | [],
+);
+
+impl_combinators_for_either!(Either5<E1, E2, E3, E4, E5> =>
+    map_e1(E1): [] | [E2, E3, E4, E5],
+    map_e2(E2): [E1] | [E3, E4, E5],
+    map_e3(E3): [E1, E2] | [E4, E5],
+    map_e4(E4): [E1, E2, E3] | [E5],
+    map_e5(E5): [E1, E2, E3, E4] | [],
+);
+
+impl_combinators_for_either!(Either6<E1, E2, E3, E4, E5, E6> =>
+    map_e1(E1): [] | [E2, E3, E4, E5, E6],
+    map_e2(E2): [E1] | [E3, E4, E5, E6],
+    map_e3(E3): [E1, E2] | [E4, E5, E6],
+    map_e4(E4): [E1, E2, E3] | [E5, E6],
+    map_e5(E5): [E1, E2, E3, E4] | [E6],
+    map_e6(E6): [E1, E2, E3, E4, E5] | [],
+);
+
+impl_combinators_for_either!(Either7<E1, E2, E3, E4, E5, E6, E7> =>
+    map_e1(E1): [] | [E2, E3, E4, E5, E6, E7],
+    map_e2(E2): [E1] | [E3, E4, E5, E6, E7],
+    map_e3(E3): [E1, E2] | [E4, E5, E6, E7],
+    map_e4(E4): [E1, E2, E3] | [E5, E6, E7],
+    map_e5(E5): [E1, E2, E3, E4] | [E6, E7],
+    map_e6(E6): [E1, E2, E3, E4, E5] | [E7],
+    map_e7(E7): [E1, E2, E3, E4, E5, E6] | [],
+);
+
+impl_combinators_for_either!(Either8<E1, E2, E3, E4, E5, E6, E7, E8> =>
+    map_e1(E1): [] | [E2, E3, E4, E5, E6, E7, E8],
+    map_e2(E2): [E1] | [E3, E4, E5, E6, E7, E8],
+    map_e3(E3): [E1, E2] | [E4, E5, E6, E7, E8],
+    map_e4(E4): [E1, E2, E3] | [E5, E6, E7, E8],
+    map_e5(E5): [E1, E2, E3, E4] | [E6, E7, E8],
+    map_e6(E6): [E1, E2, E3, E4, E5] | [E7, E8],
+    map_e7(E7): [E1, E2, E3, E4, E5, E6] | [E8],
+    map_e8(E8): [E1, E2, E3, E4, E5, E6, E7] | [],
+);
+
 macro_rules! impl_traits_for_either {
     (
         $either:ident =>
+        $rejection:ident =>
         [$($ident:ident),* $(,)?],
         $last:ident $(,)?
     ) => {
+        #[doc = concat!("The rejection used for [`", stringify!($either), "`].")]
+        ///
+        /// Returned when the request body couldn't be buffered so each branch could be tried, or
+        /// when every branch rejected the request. In the latter case every branch's rejection is
+        /// kept, in order, so none of the failure information is lost.
+        #[derive(Debug)]
+        pub enum $rejection<$($ident,)* $last> {
+            #[allow(missing_docs)]
+            Body(EitherBodyRejection),
+            /// Every branch rejected the request. Holds each branch's rejection, in order.
+            AllRejected($($ident,)* $last),
+        }
+
+        impl<$($ident,)* $last> fmt::Display for $rejection<$($ident,)* $last>
+        where
+            $($ident: fmt::Display,)*
+            $last: fmt::Display,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    Self::Body(inner) => write!(f, "{inner}"),
+                    Self::AllRejected($($ident,)* $last) => {
+                        write!(f, concat!("all branches of `", stringify!($either), "` rejected the request: "))?;
+                        $( write!(f, "{}; ", $ident)?; )*
+                        write!(f, "{}", $last)
+                    }
+                }
+            }
+        }
+
+        impl<$($ident,)* $last> std::error::Error for $rejection<$($ident,)* $last>
+        where
+            $($ident: std::error::Error + 'static,)*
+            $last: std::error::Error + 'static,
+        {
+        }
+
+        impl<$($ident,)* $last> IntoResponse for $rejection<$($ident,)* $last>
+        where
+            $($ident: IntoResponse,)*
+            $last: IntoResponse,
+        {
+            fn into_response(self) -> Response {
+                match self {
+                    Self::Body(inner) => inner.into_response(),
+                    Self::AllRejected($($ident,)* last) => {
+                        let mut responses = vec![$($ident.into_response()),*, last.into_response()];
+                        let fallback = responses.pop().expect("at least one branch");
+                        responses
+                            .into_iter()
+                            .find(|response| {
+                                !matches!(
+                                    response.status(),
+                                    StatusCode::UNSUPPORTED_MEDIA_TYPE | StatusCode::NOT_FOUND
+                                )
+                            })
+                            .unwrap_or(fallback)
+                    }
+                }
+            }
+        }
+
         #[async_trait]
         impl<S, B, $($ident),*, $last> FromRequest<S, B> for $either<$($ident),*, $last>
         where
             $($ident: FromRequest<S, B>),*,
             $last: FromRequest<S, B>,
-            B: Send,
-            S: Send,
+            S: Clone + Send,
+            B: HttpBody + From<Bytes> + Unpin + Send,
+            B::Data: Send,
+            B::Error: Into<BoxError>,
         {
-            type Rejection = $last::Rejection;
+            type Rejection = $rejection<$($ident::Rejection,)* $last::Rejection>;
 
             async fn from_request(req: &mut RequestParts<S, B>) -> Result<Self, Self::Rejection> {
+                let limit = req
+                    .extensions()
+                    .get::<EitherBufferLimit>()
+                    .map(|EitherBufferLimit(limit)| *limit)
+                    .unwrap_or(DEFAULT_BUFFER_LIMIT);
+                let bytes = buffer_body(req, limit).await.map_err($rejection::Body)?;
+
                 $(
-                    if let Ok(value) = req.extract().await {
-                        return Ok(Self::$ident(value));
-                    }
+                    let mut parts = clone_request_parts(req, B::from(bytes.clone()));
+                    let $ident = match parts.extract().await {
+                        Ok(value) => return Ok(Self::$ident(value)),
+                        Err(rejection) => rejection,
+                    };
                 )*
 
-                req.extract().await.map(Self::$last)
+                let mut parts = clone_request_parts(req, B::from(bytes));
+                let $last = match parts.extract().await {
+                    Ok(value) => return Ok(Self::$last(value)),
+                    Err(rejection) => rejection,
+                };
+
+                Err(Self::Rejection::AllRejected($($ident,)* $last))
             }
         }
 
@@ -225,10 +614,375 @@ macro_rules! impl_traits_for_either {
     };
 }
 
-impl_traits_for_either!(Either => [E1], E2);
-impl_traits_for_either!(Either3 => [E1, E2], E3);
-impl_traits_for_either!(Either4 => [E1, E2, E3], E4);
-impl_traits_for_either!(Either5 => [E1, E2, E3, E4], E5);
-impl_traits_for_either!(Either6 => [E1, E2, E3, E4, E5], E6);
-impl_traits_for_either!(Either7 => [E1, E2, E3, E4, E5, E6], E7);
-impl_traits_for_either!(Either8 => [E1, E2, E3, E4, E5, E6, E7], E8);
\ No newline at end of file
+impl_traits_for_either!(Either => EitherRejection => [E1], E2);
+impl_traits_for_either!(Either3 => Either3Rejection => [E1, E2], E3);
+impl_traits_for_either!(Either4 => Either4Rejection => [E1, E2, E3], E4);
+impl_traits_for_either!(Either5 => Either5Rejection => [E1, E2, E3, E4], E5);
+impl_traits_for_either!(Either6 => Either6Rejection => [E1, E2, E3, E4, E5], E6);
+impl_traits_for_either!(Either7 => Either7Rejection => [E1, E2, E3, E4, E5, E6], E7);
+impl_traits_for_either!(Either8 => Either8Rejection => [E1, E2, E3, E4, E5, E6, E7], E8);
+
+/// Implemented for extractors that can report which `Content-Type`s they're willing to handle, so
+/// they can be used as a branch of [`ByContentType`].
+///
+/// See the [module docs](self#content-type-dispatch) for how this is used.
+pub trait AcceptsContentType {
+    /// Returns `true` if this extractor should be tried for a request whose `Content-Type` is
+    /// `content_type` (parameters such as `; charset=utf-8` already stripped), or for a request
+    /// with no readable `Content-Type` header if `content_type` is `None`.
+    fn accepts(content_type: Option<&str>) -> bool;
+
+    /// The content type(s) this extractor accepts. Used to build the `415` response when no
+    /// branch matches the request.
+    fn accepted_content_types() -> &'static [&'static str];
+}
+
+impl<T> AcceptsContentType for axum::Json<T> {
+    fn accepts(content_type: Option<&str>) -> bool {
+        let Some(content_type) = content_type else {
+            return false;
+        };
+        // Media types are case-insensitive (RFC 7231 section 3.1.1.1), so lower-case before comparing.
+        let content_type = content_type.to_ascii_lowercase();
+        content_type == "application/json" || content_type.ends_with("+json")
+    }
+
+    fn accepted_content_types() -> &'static [&'static str] {
+        &["application/json"]
+    }
+}
+
+impl<T> AcceptsContentType for axum::Form<T> {
+    fn accepts(content_type: Option<&str>) -> bool {
+        content_type
+            .map(|content_type| content_type.eq_ignore_ascii_case("application/x-www-form-urlencoded"))
+            .unwrap_or(false)
+    }
+
+    fn accepted_content_types() -> &'static [&'static str] {
+        &["application/x-www-form-urlencoded"]
+    }
+}
+
+impl AcceptsContentType for Bytes {
+    fn accepts(_content_type: Option<&str>) -> bool {
+        true
+    }
+
+    fn accepted_content_types() -> &'static [&'static str] {
+        &["*/*"]
+    }
+}
+
+impl AcceptsContentType for String {
+    fn accepts(_content_type: Option<&str>) -> bool {
+        true
+    }
+
+    fn accepted_content_types() -> &'static [&'static str] {
+        &["*/*"]
+    }
+}
+
+/// Rejection used when no branch of a [`ByContentType`] extractor accepts the request's
+/// `Content-Type`.
+#[derive(Debug)]
+pub struct NoMatchingContentType {
+    accepted: Vec<&'static str>,
+}
+
+impl fmt::Display for NoMatchingContentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unsupported content type, expected one of: {}",
+            self.accepted.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for NoMatchingContentType {}
+
+impl IntoResponse for NoMatchingContentType {
+    fn into_response(self) -> Response {
+        (StatusCode::UNSUPPORTED_MEDIA_TYPE, self.to_string()).into_response()
+    }
+}
+
+/// The rejection used by [`ByContentType`].
+#[derive(Debug)]
+pub enum ByContentTypeRejection<E> {
+    /// No branch declared support for the request's `Content-Type`.
+    NoMatchingContentType(NoMatchingContentType),
+    /// The branch that matched the request's `Content-Type` rejected the request.
+    Rejected(E),
+}
+
+impl<E> fmt::Display for ByContentTypeRejection<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoMatchingContentType(inner) => write!(f, "{inner}"),
+            Self::Rejected(inner) => write!(f, "{inner}"),
+        }
+    }
+}
+
+impl<E> std::error::Error for ByContentTypeRejection<E> where E: std::error::Error + 'static {}
+
+impl<E> IntoResponse for ByContentTypeRejection<E>
+where
+    E: IntoResponse,
+{
+    fn into_response(self) -> Response {
+        match self {
+            Self::NoMatchingContentType(inner) => inner.into_response(),
+            Self::Rejected(inner) => inner.into_response(),
+        }
+    }
+}
+
+/// Wraps an `Either*` type to dispatch straight to the branch whose declared content type
+/// matches the request's `Content-Type` header, instead of speculatively trying each branch in
+/// order.
+///
+/// See the [module docs](self#content-type-dispatch) for an example.
+#[derive(Debug, Clone)]
+pub struct ByContentType<T>(pub T);
+
+macro_rules! impl_content_type_dispatch_for_either {
+    (
+        $either:ident =>
+        [$($ident:ident),* $(,)?],
+        $last:ident $(,)?
+    ) => {
+        #[async_trait]
+        impl<S, B, $($ident),*, $last> FromRequest<S, B> for ByContentType<$either<$($ident),*, $last>>
+        where
+            $($ident: FromRequest<S, B> + AcceptsContentType),*,
+            $last: FromRequest<S, B> + AcceptsContentType,
+            S: Send,
+            B: Send,
+        {
+            type Rejection = ByContentTypeRejection<$either<$($ident::Rejection),*, $last::Rejection>>;
+
+            async fn from_request(req: &mut RequestParts<S, B>) -> Result<Self, Self::Rejection> {
+                let content_type = req
+                    .headers()
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .map(|value| value.split(';').next().unwrap_or(value).trim());
+
+                $(
+                    if $ident::accepts(content_type) {
+                        return req
+                            .extract::<$ident>()
+                            .await
+                            .map(|value| Self($either::$ident(value)))
+                            .map_err(|err| ByContentTypeRejection::Rejected($either::$ident(err)));
+                    }
+                )*
+
+                if $last::accepts(content_type) {
+                    return req
+                        .extract::<$last>()
+                        .await
+                        .map(|value| Self($either::$last(value)))
+                        .map_err(|err| ByContentTypeRejection::Rejected($either::$last(err)));
+                }
+
+                let accepted = [$($ident::accepted_content_types(),)* $last::accepted_content_types()].concat();
+                Err(ByContentTypeRejection::NoMatchingContentType(NoMatchingContentType {
+                    accepted,
+                }))
+            }
+        }
+    };
+}
+
+impl_content_type_dispatch_for_either!(Either => [E1], E2);
+impl_content_type_dispatch_for_either!(Either3 => [E1, E2], E3);
+impl_content_type_dispatch_for_either!(Either4 => [E1, E2, E3], E4);
+impl_content_type_dispatch_for_either!(Either5 => [E1, E2, E3, E4], E5);
+impl_content_type_dispatch_for_either!(Either6 => [E1, E2, E3, E4, E5], E6);
+impl_content_type_dispatch_for_either!(Either7 => [E1, E2, E3, E4, E5, E6], E7);
+impl_content_type_dispatch_for_either!(Either8 => [E1, E2, E3, E4, E5, E6, E7], E8);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use http::Request;
+
+    #[tokio::test]
+    async fn buffered_body_is_visible_to_a_later_branch() {
+        // `Json` will fail to parse this body and reject; buffering means `String` still gets
+        // the full, unconsumed body afterwards instead of an empty one.
+        let request = Request::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from("plain text, not json"))
+            .unwrap();
+        let mut req = RequestParts::new((), request);
+
+        let either = <Either<axum::Json<serde_json::Value>, String>>::from_request(&mut req)
+            .await
+            .unwrap();
+
+        match either {
+            Either::E1(_) => panic!("`plain text, not json` shouldn't parse as JSON"),
+            Either::E2(body) => assert_eq!(body, "plain text, not json"),
+        }
+    }
+
+    #[test]
+    fn left_and_right_extract_their_own_branch_only() {
+        let left: Either<i32, &str> = Either::E1(1);
+        assert_eq!(left.left(), Some(1));
+        assert_eq!(Either::<i32, &str>::E1(1).right(), None);
+
+        let right: Either<i32, &str> = Either::E2("two");
+        assert_eq!(right.right(), Some("two"));
+        assert_eq!(Either::<i32, &str>::E2("two").left(), None);
+    }
+
+    #[test]
+    fn flip_swaps_the_two_branches() {
+        let either: Either<i32, &str> = Either::E1(1);
+        assert_eq!(either.flip(), Either::E2(1));
+
+        let either: Either<i32, &str> = Either::E2("two");
+        assert_eq!(either.flip(), Either::E1("two"));
+    }
+
+    #[test]
+    fn map_en_transforms_only_the_targeted_branch() {
+        let either: Either<i32, &str> = Either::E1(1);
+        assert_eq!(either.map_e1(|n| n + 1), Either::E1(2));
+        let either: Either<i32, &str> = Either::E2("two");
+        assert_eq!(either.map_e1(|n| n + 1), Either::E2("two"));
+
+        let either: Either8<i32, i32, i32, i32, i32, i32, i32, &str> = Either8::E8("eight");
+        assert_eq!(
+            either.map_e8(|s| s.to_uppercase()),
+            Either8::E8("EIGHT".to_owned())
+        );
+        let either: Either8<i32, i32, i32, i32, i32, i32, i32, &str> = Either8::E1(1);
+        assert_eq!(either.map_e8(|s| s.to_uppercase()), Either8::E1(1));
+    }
+
+    #[cfg(feature = "either")]
+    #[test]
+    fn either_crate_conversions_round_trip() {
+        let ours: Either<i32, &str> = Either::E1(1);
+        let theirs: either::Either<i32, &str> = ours.clone().into();
+        assert_eq!(theirs, either::Either::Left(1));
+        assert_eq!(Either::from(theirs), ours);
+
+        let ours: Either<i32, &str> = Either::E2("two");
+        let theirs: either::Either<i32, &str> = ours.clone().into();
+        assert_eq!(theirs, either::Either::Right("two"));
+        assert_eq!(Either::from(theirs), ours);
+    }
+
+    #[tokio::test]
+    async fn buffering_a_body_over_the_limit_is_rejected_with_413() {
+        let mut request = Request::builder()
+            .body(Body::from(vec![0u8; DEFAULT_BUFFER_LIMIT + 1]))
+            .unwrap();
+        request.extensions_mut().insert(EitherBufferLimit(16));
+        let mut req = RequestParts::new((), request);
+
+        let rejection = <Either<String, Bytes>>::from_request(&mut req)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            rejection.into_response().status(),
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+
+    #[derive(Debug)]
+    struct Reject(StatusCode);
+
+    impl IntoResponse for Reject {
+        fn into_response(self) -> Response {
+            self.0.into_response()
+        }
+    }
+
+    #[test]
+    fn rejection_into_response_prefers_the_first_non_404_or_415_status() {
+        let rejection: Either3Rejection<Reject, Reject, Reject> = Either3Rejection::AllRejected(
+            Reject(StatusCode::NOT_FOUND),
+            Reject(StatusCode::BAD_REQUEST),
+            Reject(StatusCode::UNSUPPORTED_MEDIA_TYPE),
+        );
+
+        assert_eq!(rejection.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn rejection_into_response_falls_back_to_the_last_branch_if_none_stand_out() {
+        let rejection: Either3Rejection<Reject, Reject, Reject> = Either3Rejection::AllRejected(
+            Reject(StatusCode::NOT_FOUND),
+            Reject(StatusCode::UNSUPPORTED_MEDIA_TYPE),
+            Reject(StatusCode::NOT_FOUND),
+        );
+
+        assert_eq!(rejection.into_response().status(), StatusCode::NOT_FOUND);
+    }
+
+    type JsonOrForm = Either<axum::Json<serde_json::Value>, axum::Form<std::collections::HashMap<String, String>>>;
+
+    #[tokio::test]
+    async fn by_content_type_dispatches_straight_to_the_matching_branch() {
+        let request = Request::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"hello":"world"}"#))
+            .unwrap();
+        let mut req = RequestParts::new((), request);
+
+        let ByContentType(either) = <ByContentType<JsonOrForm>>::from_request(&mut req)
+            .await
+            .unwrap();
+
+        match either {
+            Either::E1(axum::Json(value)) => assert_eq!(value["hello"], "world"),
+            Either::E2(_) => panic!("expected the JSON branch given `Content-Type: application/json`"),
+        }
+    }
+
+    #[tokio::test]
+    async fn by_content_type_ignores_parameters_like_charset() {
+        let request = Request::builder()
+            .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+            .body(Body::from(r#"{"hello":"world"}"#))
+            .unwrap();
+        let mut req = RequestParts::new((), request);
+
+        assert!(<ByContentType<JsonOrForm>>::from_request(&mut req)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn by_content_type_rejects_with_415_when_nothing_matches() {
+        let request = Request::builder()
+            .header(header::CONTENT_TYPE, "application/octet-stream")
+            .body(Body::from(vec![1, 2, 3]))
+            .unwrap();
+        let mut req = RequestParts::new((), request);
+
+        let rejection = <ByContentType<JsonOrForm>>::from_request(&mut req)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            rejection.into_response().status(),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+    }
+}
\ No newline at end of file